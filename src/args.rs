@@ -1,4 +1,5 @@
 use std::path::PathBuf;
+use std::str::FromStr;
 
 use chrono::NaiveDateTime;
 use clap::Parser;
@@ -175,4 +176,86 @@ pub struct OnefileArgs {
     /// This is generally not wanted
     #[arg(long, default_value_t = false)]
     pub include_lock: bool,
+
+    /// Ask for confirmation before writing if the output would exceed this size.
+    /// Accepts a number of bytes, or a number of tokens when suffixed with `tokens`.
+    /// In non-interactive mode, the command aborts instead of prompting.
+    ///
+    /// Example:
+    ///   cargo onefile --confirm-over 10000000
+    ///   cargo onefile --confirm-over 200000tokens
+    #[arg(long)]
+    pub confirm_over: Option<SizeThreshold>,
+}
+
+/// A size limit on the generated output, measured in bytes or estimated tokens.
+#[derive(Debug, Clone, Copy)]
+pub enum SizeThreshold {
+    Bytes(u64),
+    Tokens(u64),
+}
+
+impl FromStr for SizeThreshold {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_ascii_lowercase();
+        let (num, tokens) = if let Some(num) = normalized.strip_suffix("tokens") {
+            (num, true)
+        } else {
+            (
+                normalized.strip_suffix("bytes").unwrap_or(&normalized),
+                false,
+            )
+        };
+        let value = num.trim().parse::<u64>().map_err(|_| {
+            format!("invalid threshold `{s}`, expected e.g. `1000000` or `200000tokens`")
+        })?;
+        Ok(if tokens {
+            Self::Tokens(value)
+        } else {
+            Self::Bytes(value)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<SizeThreshold, String> {
+        s.parse()
+    }
+
+    #[test]
+    fn size_threshold_parses_bytes() {
+        assert!(matches!(parse("1000"), Ok(SizeThreshold::Bytes(1000))));
+        assert!(matches!(parse("1000bytes"), Ok(SizeThreshold::Bytes(1000))));
+    }
+
+    #[test]
+    fn size_threshold_parses_tokens() {
+        assert!(matches!(
+            parse("200000tokens"),
+            Ok(SizeThreshold::Tokens(200_000))
+        ));
+        assert!(matches!(
+            parse("200000 TOKENS"),
+            Ok(SizeThreshold::Tokens(200_000))
+        ));
+    }
+
+    #[test]
+    fn size_threshold_rejects_invalid() {
+        assert!(parse("").is_err());
+        assert!(parse("tokens").is_err());
+        let err = parse("-5").unwrap_err();
+        assert!(err.contains("`-5`"), "{err}");
+    }
+
+    #[test]
+    fn size_threshold_error_shows_original_input() {
+        let err = parse(" 5 Tokenz").unwrap_err();
+        assert!(err.contains("` 5 Tokenz`"), "{err}");
+    }
 }
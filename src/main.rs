@@ -7,12 +7,13 @@
 )]
 
 use anyhow::{bail, Result};
-use args::{Commands, OnefileArgs};
+use args::{Commands, OnefileArgs, SizeThreshold};
 use chrono::{DateTime, NaiveDateTime, Utc};
 use clap::Parser;
 use ignore::{WalkBuilder, WalkState};
 use rayon::prelude::*;
-use std::io::{BufRead, BufWriter, Write};
+use std::collections::HashSet;
+use std::io::{BufRead, BufWriter, IsTerminal, Write};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
@@ -87,7 +88,7 @@ fn generate_output(
 ) -> Result<()> {
     let head = args.head.as_ref().map(std::fs::read).transpose()?;
     let table_of_contents = args.table_of_contents.then(|| {
-        generate_table_of_contents(&file_contents, head.map_or(0, |h| h.len())).into_bytes()
+        generate_table_of_contents(&file_contents, head.as_ref().map_or(0, Vec::len)).into_bytes()
     });
 
     if let Some(start) = start {
@@ -95,42 +96,140 @@ fn generate_output(
         return Ok(());
     }
 
+    if let Some(threshold) = args.confirm_over {
+        let size = projected_output_size(
+            args,
+            &file_contents,
+            head.as_deref(),
+            metadata.as_ref(),
+            table_of_contents.as_deref(),
+        )?;
+        if !confirm_output_size(size, threshold)? {
+            bail!("Aborted, output was not written");
+        }
+    }
+
     let cursor = if args.stdout {
         &mut BufWriter::new(std::io::stdout()) as &mut dyn Write
     } else {
         &mut BufWriter::new(std::fs::File::create(&args.output)?) as &mut dyn Write
     };
 
-    write_output(cursor, args, file_contents, metadata, table_of_contents)?;
+    write_output(
+        cursor,
+        args,
+        &file_contents,
+        head.as_deref(),
+        metadata.as_ref(),
+        table_of_contents.as_deref(),
+    )?;
 
     Ok(())
 }
 
+/// A `Write` sink that discards its input and only counts the bytes written.
+#[derive(Default)]
+struct ByteCounter(u64);
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Computes the number of bytes `write_output` will produce for the given inputs.
+fn projected_output_size(
+    args: &OnefileArgs,
+    file_contents: &[(PathBuf, Vec<u8>)],
+    head: Option<&[u8]>,
+    metadata: Option<&ProjectMetadata>,
+    table_of_contents: Option<&[u8]>,
+) -> Result<u64> {
+    let mut counter = ByteCounter::default();
+    write_output(
+        &mut counter,
+        args,
+        file_contents,
+        head,
+        metadata,
+        table_of_contents,
+    )?;
+    Ok(counter.0)
+}
+
+/// Rough token estimate for LLM context windows, assuming ~4 bytes per token.
+const fn estimate_tokens(bytes: u64) -> u64 {
+    bytes.div_ceil(4)
+}
+
+/// Whether an output of `size` bytes is over `threshold`.
+const fn exceeds(size: u64, threshold: SizeThreshold) -> bool {
+    match threshold {
+        SizeThreshold::Bytes(max) => size > max,
+        SizeThreshold::Tokens(max) => estimate_tokens(size) > max,
+    }
+}
+
+/// Reports the projected output size and, if it exceeds `threshold`, asks the user
+/// whether to continue. Returns `Ok(false)` if the output should not be written.
+fn confirm_output_size(size: u64, threshold: SizeThreshold) -> Result<bool> {
+    eprintln!(
+        "Projected output: {size} bytes (~{} tokens)",
+        estimate_tokens(size)
+    );
+
+    if !exceeds(size, threshold) {
+        return Ok(true);
+    }
+
+    let stdin = std::io::stdin();
+    if !stdin.is_terminal() {
+        eprintln!(
+            "Output exceeds the `confirm_over` threshold and no terminal is available to confirm"
+        );
+        return Ok(false);
+    }
+
+    eprint!("Output exceeds the `confirm_over` threshold, continue? [y/N] ");
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    stdin.lock().read_line(&mut answer)?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
 fn write_output(
     cursor: &mut dyn Write,
     args: &OnefileArgs,
-    file_contents: Vec<(PathBuf, Vec<u8>)>,
-    metadata: Option<ProjectMetadata>,
-    table_of_contents: Option<Vec<u8>>,
+    file_contents: &[(PathBuf, Vec<u8>)],
+    head: Option<&[u8]>,
+    metadata: Option<&ProjectMetadata>,
+    table_of_contents: Option<&[u8]>,
 ) -> Result<()> {
-    if let Some(head) = &args.head {
-        let head_content = std::fs::read(head)?;
-        cursor.write(&head_content)?;
+    if let Some(head_content) = head {
+        cursor.write_all(head_content)?;
     }
 
     if let Some(metadata) = metadata {
         let meta = metadata.format();
-        cursor.write(meta.as_bytes())?;
+        cursor.write_all(meta.as_bytes())?;
     }
 
     if let Some(toc) = table_of_contents {
-        cursor.write(&toc)?;
+        cursor.write_all(toc)?;
     }
 
     for (path, contents) in file_contents {
         writeln!(cursor, "{} {}", &args.separator, path.display())?;
-        cursor.write(&contents)?;
-        cursor.write(b"\n")?;
+        cursor.write_all(contents)?;
+        cursor.write_all(b"\n")?;
     }
 
     Ok(())
@@ -298,6 +397,13 @@ fn collect_source_files(args: &OnefileArgs) -> Result<Vec<(PathBuf, Vec<u8>)>> {
     // If there are any directories, get the files from them
     reduce_dir_list(&mut source_files, args)?;
 
+    // Overlapping search paths can yield the same file more than once
+    let duplicates = dedup_paths(&mut source_files);
+    if duplicates > 0 {
+        let noun = if duplicates == 1 { "file" } else { "files" };
+        eprintln!("Skipped {duplicates} duplicate {noun}");
+    }
+
     if let Some(max_files) = args.max_files {
         if source_files.len() > max_files {
             eprintln!(
@@ -327,6 +433,18 @@ fn collect_source_files(args: &OnefileArgs) -> Result<Vec<(PathBuf, Vec<u8>)>> {
     Ok(file_contents)
 }
 
+/// Removes paths that point to the same file, keeping the shortest spelling
+/// (ties broken lexicographically) so the result does not depend on walk order.
+/// Paths are compared by their canonical form, falling back to the path as given
+/// if it cannot be canonicalized. Returns the number of paths removed.
+fn dedup_paths(paths: &mut Vec<PathBuf>) -> usize {
+    let before = paths.len();
+    paths.sort_by_cached_key(|path| (path.as_os_str().len(), path.clone()));
+    let mut seen = HashSet::with_capacity(before);
+    paths.retain(|path| seen.insert(std::fs::canonicalize(path).unwrap_or_else(|_| path.clone())));
+    before - paths.len()
+}
+
 fn setup_walker(walker: &mut WalkBuilder, args: &OnefileArgs) {
     for excl in &args.exclude {
         walker.add(excl);
@@ -377,3 +495,79 @@ fn reduce_dir_list(paths: &mut Vec<PathBuf>, args: &OnefileArgs) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn projected_output_size_matches_written_output() {
+        let args = OnefileArgs::parse_from(["onefile", "--separator", "// File:"]);
+        let file_contents = vec![
+            (PathBuf::from("src/main.rs"), b"fn main() {}\n".to_vec()),
+            (PathBuf::from("src/lib.rs"), Vec::new()),
+        ];
+        let head: &[u8] = b"// header\n";
+        let toc = generate_table_of_contents(&file_contents, head.len()).into_bytes();
+
+        let mut written = Vec::new();
+        write_output(
+            &mut written,
+            &args,
+            &file_contents,
+            Some(head),
+            None,
+            Some(&toc),
+        )
+        .unwrap();
+        let projected =
+            projected_output_size(&args, &file_contents, Some(head), None, Some(&toc)).unwrap();
+
+        assert_eq!(projected, written.len() as u64);
+    }
+
+    #[test]
+    fn exceeds_bytes_threshold() {
+        assert!(!exceeds(100, SizeThreshold::Bytes(100)));
+        assert!(exceeds(101, SizeThreshold::Bytes(100)));
+    }
+
+    #[test]
+    fn exceeds_tokens_threshold_rounds_up() {
+        // 400 bytes is exactly 100 tokens, 401 bytes rounds up to 101
+        assert!(!exceeds(400, SizeThreshold::Tokens(100)));
+        assert!(exceeds(401, SizeThreshold::Tokens(100)));
+        assert!(!exceeds(0, SizeThreshold::Tokens(0)));
+        assert!(exceeds(1, SizeThreshold::Tokens(0)));
+    }
+
+    #[test]
+    fn dedup_paths_removes_overlapping_spellings() {
+        // Relative to the package root, which is the working directory for tests
+        let dir = PathBuf::from(format!("target/dedup-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("a.rs");
+        std::fs::write(&file, "").unwrap();
+
+        let mut paths = vec![
+            std::fs::canonicalize(&file).unwrap(),
+            PathBuf::from(".").join(&file),
+            file.clone(),
+        ];
+        let removed = dedup_paths(&mut paths);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(removed, 2);
+        assert_eq!(paths, vec![file]);
+    }
+
+    #[test]
+    fn dedup_paths_falls_back_to_raw_path() {
+        let missing = PathBuf::from("does/not/exist.rs");
+        let other = PathBuf::from("does/not/exist_either.rs");
+        let mut paths = vec![missing.clone(), other.clone(), missing.clone()];
+
+        assert_eq!(dedup_paths(&mut paths), 1);
+        assert_eq!(paths, vec![missing, other]);
+    }
+}